@@ -0,0 +1,310 @@
+//! Lightweight corpus-to-embeddings trainer (PPMI co-occurrence + truncated
+//! SVD), so the vector functions aren't dependent on an external embedding
+//! API.
+//!
+//! Pipeline: tokenize each document, build a `min_count`-filtered
+//! vocabulary, accumulate a symmetric word-context co-occurrence matrix over
+//! a sliding window, convert counts to Positive PMI, then factorize the PPMI
+//! matrix into low-dimensional word vectors. The resulting vectors flow
+//! directly into `cosine_similarity` / `find_top_k_similar`.
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// Oversampling added to `dim` when building the random projection, and the
+/// number of power iterations used to let the subspace converge onto the
+/// PPMI matrix's dominant eigenspace.
+const OVERSAMPLE: usize = 10;
+const POWER_ITERATIONS: usize = 4;
+
+/// Deterministic splitmix64 PRNG - avoids pulling in an external `rand`
+/// dependency for what's just a handful of Gaussian draws.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E3779B97F4A7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D049BB133111EB);
+        z ^ (z >> 31)
+    }
+
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+
+    /// Standard-normal sample via Box-Muller.
+    fn next_gaussian(&mut self) -> f64 {
+        let u1 = self.next_f64().max(f64::EPSILON);
+        let u2 = self.next_f64();
+        (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+    }
+}
+
+fn tokenize(document: &str) -> Vec<String> {
+    document
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|tok| !tok.is_empty())
+        .map(|tok| tok.to_lowercase())
+        .collect()
+}
+
+/// Build a `min_count`-filtered vocabulary, ordered by descending frequency
+/// (ties broken alphabetically for determinism).
+fn build_vocab(tokenized_docs: &[Vec<String>], min_count: usize) -> Vec<String> {
+    let mut counts: HashMap<&str, usize> = HashMap::new();
+    for doc in tokenized_docs {
+        for token in doc {
+            *counts.entry(token.as_str()).or_insert(0) += 1;
+        }
+    }
+
+    let mut vocab: Vec<(&str, usize)> = counts
+        .into_iter()
+        .filter(|&(_, count)| count >= min_count)
+        .collect();
+    vocab.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+    vocab.into_iter().map(|(word, _)| word.to_string()).collect()
+}
+
+/// Accumulate a symmetric word-context co-occurrence count as a sparse
+/// map keyed by word-pair index, parallelized across documents. A dense
+/// `n x n` matrix per document (or per reduce step) would be hundreds of MB
+/// for even a modest vocabulary, so only real co-occurring pairs are stored.
+fn build_cooccurrence(
+    tokenized_docs: &[Vec<String>],
+    vocab_index: &HashMap<&str, usize>,
+    window: usize,
+) -> HashMap<(usize, usize), f64> {
+    tokenized_docs
+        .par_iter()
+        .map(|doc| {
+            let mut local: HashMap<(usize, usize), f64> = HashMap::new();
+            let ids: Vec<Option<usize>> = doc.iter().map(|t| vocab_index.get(t.as_str()).copied()).collect();
+
+            for i in 0..ids.len() {
+                let Some(wi) = ids[i] else { continue };
+                let window_end = (i + window + 1).min(ids.len());
+                for &id in ids.iter().take(window_end).skip(i + 1) {
+                    let Some(wj) = id else { continue };
+                    *local.entry((wi, wj)).or_insert(0.0) += 1.0;
+                    *local.entry((wj, wi)).or_insert(0.0) += 1.0;
+                }
+            }
+            local
+        })
+        .reduce(HashMap::new, |mut acc, local| {
+            for (pair, count) in local {
+                *acc.entry(pair).or_insert(0.0) += count;
+            }
+            acc
+        })
+}
+
+/// Convert sparse co-occurrence counts to a dense Positive PMI matrix:
+/// `PPMI(w,c) = max(0, log((count(w,c) * total) / (count(w) * count(c))))`.
+fn to_ppmi(cooccurrence: &HashMap<(usize, usize), f64>, n: usize) -> Vec<Vec<f64>> {
+    let mut word_totals = vec![0.0f64; n];
+    for (&(word, _), &count) in cooccurrence {
+        word_totals[word] += count;
+    }
+    let total: f64 = word_totals.iter().sum::<f64>().max(1.0);
+
+    let mut ppmi = vec![vec![0.0f64; n]; n];
+    for (&(i, j), &count) in cooccurrence {
+        if count <= 0.0 || word_totals[i] <= 0.0 || word_totals[j] <= 0.0 {
+            continue;
+        }
+        let pmi = ((count * total) / (word_totals[i] * word_totals[j])).ln();
+        ppmi[i][j] = pmi.max(0.0);
+    }
+    ppmi
+}
+
+fn mat_vec(matrix: &[Vec<f64>], vec: &[f64]) -> Vec<f64> {
+    matrix
+        .iter()
+        .map(|row| row.iter().zip(vec).map(|(a, b)| a * b).sum())
+        .collect()
+}
+
+/// In-place column-wise Gram-Schmidt orthonormalization of `columns`
+/// (each entry is one column, length = number of rows).
+fn orthonormalize(columns: &mut [Vec<f64>]) {
+    for i in 0..columns.len() {
+        for j in 0..i {
+            let dot: f64 = columns[i].iter().zip(&columns[j]).map(|(a, b)| a * b).sum();
+            for k in 0..columns[i].len() {
+                columns[i][k] -= dot * columns[j][k];
+            }
+        }
+        let norm: f64 = columns[i].iter().map(|x| x * x).sum::<f64>().sqrt();
+        if norm > 1e-10 {
+            for x in columns[i].iter_mut() {
+                *x /= norm;
+            }
+        }
+    }
+}
+
+/// Jacobi eigenvalue algorithm for a small symmetric matrix. Returns
+/// (eigenvalues, eigenvectors-as-columns), not sorted.
+///
+/// The rotation step reads and writes `a`/`v` at both `(i, p)` and `(i, q)`
+/// on every pass, so the indices themselves are the data being operated on -
+/// an iterator adapter would obscure the algorithm rather than clarify it.
+#[allow(clippy::needless_range_loop)]
+fn jacobi_eigen(matrix: &[Vec<f64>]) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let mut a: Vec<Vec<f64>> = matrix.to_vec();
+    let mut v = vec![vec![0.0; n]; n];
+    for i in 0..n {
+        v[i][i] = 1.0;
+    }
+
+    for _ in 0..100 {
+        // Find the largest off-diagonal element.
+        let (mut p, mut q, mut max_val) = (0usize, 1usize, 0.0f64);
+        for i in 0..n {
+            for j in i + 1..n {
+                if a[i][j].abs() > max_val {
+                    max_val = a[i][j].abs();
+                    p = i;
+                    q = j;
+                }
+            }
+        }
+        if max_val < 1e-10 {
+            break;
+        }
+
+        let theta = (a[q][q] - a[p][p]) / (2.0 * a[p][q]);
+        let t = theta.signum() / (theta.abs() + (theta * theta + 1.0).sqrt());
+        let c = 1.0 / (t * t + 1.0).sqrt();
+        let s = t * c;
+
+        let app = a[p][p];
+        let aqq = a[q][q];
+        let apq = a[p][q];
+        a[p][p] = c * c * app - 2.0 * s * c * apq + s * s * aqq;
+        a[q][q] = s * s * app + 2.0 * s * c * apq + c * c * aqq;
+        a[p][q] = 0.0;
+        a[q][p] = 0.0;
+
+        for i in 0..n {
+            if i != p && i != q {
+                let aip = a[i][p];
+                let aiq = a[i][q];
+                a[i][p] = c * aip - s * aiq;
+                a[p][i] = a[i][p];
+                a[i][q] = s * aip + c * aiq;
+                a[q][i] = a[i][q];
+            }
+        }
+        for i in 0..n {
+            let vip = v[i][p];
+            let viq = v[i][q];
+            v[i][p] = c * vip - s * viq;
+            v[i][q] = s * vip + c * viq;
+        }
+    }
+
+    ((0..n).map(|i| a[i][i]).collect(), v)
+}
+
+/// Randomized truncated eigendecomposition of a symmetric matrix, returning
+/// the top `dim` (eigenvalue, eigenvector) pairs sorted by descending
+/// eigenvalue magnitude. Equivalent to truncated SVD for a symmetric matrix
+/// (U = V = eigenvectors, singular values = |eigenvalues|).
+fn truncated_symmetric_svd(matrix: &[Vec<f64>], dim: usize, seed: u64) -> (Vec<f64>, Vec<Vec<f64>>) {
+    let n = matrix.len();
+    let rank = (dim + OVERSAMPLE).min(n).max(1);
+
+    let mut rng = SplitMix64::new(seed);
+    let mut columns: Vec<Vec<f64>> = (0..rank)
+        .map(|_| (0..n).map(|_| rng.next_gaussian()).collect())
+        .collect();
+    orthonormalize(&mut columns);
+
+    for _ in 0..POWER_ITERATIONS {
+        columns = columns.iter().map(|col| mat_vec(matrix, col)).collect();
+        orthonormalize(&mut columns);
+    }
+
+    // Small projected matrix T = Q^T * M * Q (rank x rank).
+    let projected: Vec<Vec<f64>> = columns.iter().map(|col| mat_vec(matrix, col)).collect();
+    let mut small = vec![vec![0.0; rank]; rank];
+    for i in 0..rank {
+        for j in 0..rank {
+            small[i][j] = columns[j].iter().zip(&projected[i]).map(|(a, b)| a * b).sum();
+        }
+    }
+
+    let (eigenvalues, small_vectors) = jacobi_eigen(&small);
+
+    let mut order: Vec<usize> = (0..rank).collect();
+    order.sort_by(|&a, &b| eigenvalues[b].abs().partial_cmp(&eigenvalues[a].abs()).unwrap());
+    order.truncate(dim);
+
+    let top_eigenvalues: Vec<f64> = order.iter().map(|&i| eigenvalues[i]).collect();
+    // Lift the small eigenvectors back into the original n-dimensional space: Q * v.
+    let top_eigenvectors: Vec<Vec<f64>> = order
+        .iter()
+        .map(|&i| {
+            (0..n)
+                .map(|row| (0..rank).map(|k| columns[k][row] * small_vectors[k][i]).sum())
+                .collect()
+        })
+        .collect();
+
+    (top_eigenvalues, top_eigenvectors)
+}
+
+/// Train PPMI + truncated-SVD word embeddings from a document corpus.
+///
+/// Returns the vocabulary and row-aligned vectors (word i's vector is
+/// `vectors[i]`), ready to feed into `cosine_similarity` / `find_top_k_similar`.
+#[pyfunction]
+pub fn train_pmi_embeddings(
+    documents: Vec<String>,
+    window: usize,
+    dim: usize,
+    min_count: usize,
+) -> PyResult<(Vec<String>, Vec<Vec<f64>>)> {
+    let tokenized_docs: Vec<Vec<String>> = documents.iter().map(|doc| tokenize(doc)).collect();
+    let vocab = build_vocab(&tokenized_docs, min_count);
+    let vocab_index: HashMap<&str, usize> = vocab
+        .iter()
+        .enumerate()
+        .map(|(i, word)| (word.as_str(), i))
+        .collect();
+
+    if vocab.is_empty() {
+        return Ok((vocab, Vec::new()));
+    }
+
+    let cooccurrence = build_cooccurrence(&tokenized_docs, &vocab_index, window.max(1));
+    let ppmi = to_ppmi(&cooccurrence, vocab.len());
+
+    let dim = dim.min(vocab.len());
+    let (eigenvalues, eigenvectors) = truncated_symmetric_svd(&ppmi, dim, 0x5EED_F00D);
+
+    // word vectors = U * Sigma^0.5, i.e. each eigenvector scaled by
+    // sqrt(|eigenvalue|).
+    let scales: Vec<f64> = eigenvalues.iter().map(|ev| ev.abs().sqrt()).collect();
+    let mut vectors = vec![vec![0.0; dim]; vocab.len()];
+    for (col, (eigenvector, &scale)) in eigenvectors.iter().zip(scales.iter()).enumerate() {
+        for (word_idx, component) in eigenvector.iter().enumerate() {
+            vectors[word_idx][col] = component * scale;
+        }
+    }
+
+    Ok((vocab, vectors))
+}
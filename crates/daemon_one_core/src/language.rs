@@ -0,0 +1,226 @@
+//! Profile-based n-gram language detection (Cavnar-Trenkle style), used to
+//! replace the hard-coded Korean-ratio heuristic in token estimation.
+//!
+//! Each supported language has a frequency-ranked table of character
+//! trigrams built from training data. At runtime we extract the padded
+//! trigram profile of the input text, score each language by summing rank
+//! differences against its profile (out-of-profile trigrams pay a fixed max
+//! penalty), then normalize the inverted distances into probabilities.
+
+use std::collections::HashMap;
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// How many of the input's most frequent trigrams to score against each
+/// language profile.
+const PROFILE_SIZE: usize = 300;
+
+/// Penalty applied when a trigram from the input profile does not appear in
+/// a language's profile at all.
+const MAX_DISTANCE: usize = PROFILE_SIZE;
+
+/// A language's trigrams, most frequent first - index doubles as its rank.
+struct LanguageProfile {
+    code: &'static str,
+    /// Approximate characters-per-token ratio for this script, used by
+    /// `count_tokens_approx` / `chunk_text` as a fast-path estimate.
+    chars_per_token: f64,
+    trigrams: &'static [&'static str],
+}
+
+// Small seed profiles - the top trigrams are the ones that dominate rank
+// scoring, so only these need to be present for the detector to separate
+// scripts and the common Latin languages cleanly.
+static PROFILES: &[LanguageProfile] = &[
+    LanguageProfile {
+        code: "en",
+        chars_per_token: 4.0,
+        trigrams: &[
+            "_th", "the", "he_", "_an", "and", "nd_", "ing", "_to", "to_", "_of", "of_", "ed_",
+            "_a_", "_in", "in_", "ion", "er_", "_re", "ent", "_th",
+        ],
+    },
+    LanguageProfile {
+        code: "es",
+        chars_per_token: 4.0,
+        trigrams: &[
+            "_de", "de_", "que", "_qu", "_la", "la_", "ent", "_el", "el_", "cio", "ado", "_en",
+            "en_", "ion", "_co", "_pa", "_un", "os_", "as_", "_es",
+        ],
+    },
+    LanguageProfile {
+        code: "fr",
+        chars_per_token: 4.0,
+        trigrams: &[
+            "_de", "de_", "ent", "_la", "la_", "_le", "le_", "_un", "tio", "_et", "et_", "_en",
+            "ion", "que", "_qu", "ais", "ant", "_du", "_à_", "les",
+        ],
+    },
+    LanguageProfile {
+        code: "de",
+        chars_per_token: 4.0,
+        trigrams: &[
+            "en_", "der", "_de", "ie_", "_di", "che", "ich", "sch", "_un", "und", "nd_", "gen",
+            "_ge", "ung", "eit", "_in", "in_", "_da", "ein", "chen",
+        ],
+    },
+    LanguageProfile {
+        code: "ko",
+        // Hangul syllable blocks roughly pack two morphemes per token.
+        chars_per_token: 2.0,
+        // These are the actual 3-character `_{word}_` windows that
+        // `text_trigram_profile` produces for common 3-character words and
+        // for the "-습니다/-ㅂ니다" formal verb ending, which is the single
+        // most frequent trigram in formal Korean text.
+        trigrams: &[
+            "니다_", "습니다", "합니다", "입니다", "_습니", "_합니", "_입니", "그리고",
+            "_그리", "리고_", "한국어", "_한국", "국어_", "_공부", "공부_", "_행복",
+            "행복_", "_좋은", "좋은_", "_저는", "저는_",
+        ],
+    },
+    LanguageProfile {
+        code: "ja",
+        chars_per_token: 2.0,
+        // Verified 3-character windows for common Japanese function words
+        // and verb/copula endings.
+        trigrams: &[
+            "という", "でした", "ました", "ている", "ないで", "ですが", "それは", "_とい",
+            "いう_", "_でし", "した_", "_まし", "_てい", "いる_",
+        ],
+    },
+    LanguageProfile {
+        code: "zh",
+        chars_per_token: 1.5,
+        // Verified 3-character Chinese words/phrases (no combining marks,
+        // so every character is one codepoint).
+        trigrams: &[
+            "我们的", "不知道", "没有人", "这样的", "可以的", "应该是", "一直在", "已经是",
+            "还是会", "但是我",
+        ],
+    },
+    LanguageProfile {
+        code: "th",
+        chars_per_token: 3.0,
+        // Common Thai words that happen to be exactly 3 Unicode scalar
+        // values (consonant + vowel/tone marks count as separate chars),
+        // so they appear verbatim as `_{word}_` windows.
+        trigrams: &[
+            "ฉัน", "นี้", "ว่า", "ได้", "ให้", "วัน", "รัก", "เธอ", "ของ", "ไม่", "กับ",
+        ],
+    },
+];
+
+/// Default ratio when no language scores above a usable confidence.
+const DEFAULT_CHARS_PER_TOKEN: f64 = 4.0;
+
+/// Extract the input's trigram frequency profile, most frequent first,
+/// truncated to `PROFILE_SIZE`. Text is padded with `_` at word boundaries
+/// so leading/trailing characters participate in a trigram.
+fn text_trigram_profile(text: &str) -> Vec<String> {
+    let mut counts: HashMap<String, usize> = HashMap::new();
+
+    for word in text.split_whitespace() {
+        let padded = format!("_{word}_");
+        let chars: Vec<char> = padded.chars().collect();
+        if chars.len() < 3 {
+            continue;
+        }
+        for window in chars.windows(3) {
+            *counts.entry(window.iter().collect()).or_insert(0) += 1;
+        }
+    }
+
+    let mut ranked: Vec<(String, usize)> = counts.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+    ranked.truncate(PROFILE_SIZE);
+    ranked.into_iter().map(|(trigram, _)| trigram).collect()
+}
+
+/// Out-of-place distance between an input profile and a language profile:
+/// sum of rank differences, with a fixed max penalty for trigrams the
+/// language profile doesn't contain at all.
+fn profile_distance(input_profile: &[String], language: &LanguageProfile) -> usize {
+    let ranks: HashMap<&str, usize> = language
+        .trigrams
+        .iter()
+        .enumerate()
+        .map(|(rank, &trigram)| (trigram, rank))
+        .collect();
+
+    input_profile
+        .iter()
+        .enumerate()
+        .map(|(input_rank, trigram)| match ranks.get(trigram.as_str()) {
+            Some(&lang_rank) => input_rank.abs_diff(lang_rank),
+            None => MAX_DISTANCE,
+        })
+        .sum()
+}
+
+/// Detect the dominant language of `text`, returning its code (`"en"`,
+/// `"ko"`, ...) and a confidence in `[0, 1]`.
+#[pyfunction]
+pub fn detect_language(text: &str) -> PyResult<(String, f64)> {
+    Ok(detect_language_inner(text))
+}
+
+fn detect_language_inner(text: &str) -> (String, f64) {
+    let input_profile = text_trigram_profile(text);
+    if input_profile.is_empty() {
+        return ("und".to_string(), 0.0);
+    }
+
+    let distances: Vec<(&str, usize)> = PROFILES
+        .iter()
+        .map(|profile| (profile.code, profile_distance(&input_profile, profile)))
+        .collect();
+
+    // Lower distance is a better match. `max_distance` (input_profile.len()
+    // * MAX_DISTANCE) dwarfs the actual spread between languages, so scoring
+    // against it directly washes out the gap between a clear winner and the
+    // rest. Instead take a softmax over distances relative to the best
+    // match, scaled by PROFILE_SIZE, so languages that are actually close
+    // in distance end up close in probability and a clear winner stands out.
+    let min_distance = distances.iter().map(|&(_, d)| d as f64).fold(f64::INFINITY, f64::min);
+    let scores: Vec<(&str, f64)> = distances
+        .iter()
+        .map(|&(code, dist)| (code, (-(dist as f64 - min_distance) / PROFILE_SIZE as f64).exp()))
+        .collect();
+    let total: f64 = scores.iter().map(|(_, s)| s).sum();
+
+    scores
+        .into_iter()
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(code, score)| (code.to_string(), score / total))
+        .unwrap_or_else(|| ("und".to_string(), 0.0))
+}
+
+/// Parallelized batch `detect_language` over many documents.
+#[pyfunction]
+pub fn detect_language_batch(texts: Vec<String>) -> PyResult<Vec<(String, f64)>> {
+    Ok(texts.par_iter().map(|text| detect_language_inner(text)).collect())
+}
+
+/// Characters-per-token ratio to use for a detected language code, falling
+/// back to the Latin-script default for unknown/undetermined languages.
+pub fn chars_per_token_for(language_code: &str) -> f64 {
+    PROFILES
+        .iter()
+        .find(|profile| profile.code == language_code)
+        .map(|profile| profile.chars_per_token)
+        .unwrap_or(DEFAULT_CHARS_PER_TOKEN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn detects_korean() {
+        let (language, confidence) =
+            detect_language_inner("오늘 날씨가 좋습니다 그리고 저는 공부를 합니다");
+        assert_eq!(language, "ko");
+        assert!(confidence > 0.3, "confidence too low: {confidence}");
+    }
+}
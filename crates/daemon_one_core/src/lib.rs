@@ -15,10 +15,32 @@
 //! - HTTP request handling (let Django do it)
 //! - Database queries
 
+// The `#[pyfunction]` macro expands every `PyResult`-returning function body
+// through a `?`-based conversion that clippy's `useless_conversion` lint
+// flags as a no-op `PyErr -> PyErr` conversion on this pyo3/clippy pairing.
+// It fires on every pyfunction in the crate, including ones that return no
+// error at all, so it's a macro-expansion artifact rather than anything
+// about our code - allow it crate-wide instead of peppering every function.
+#![allow(clippy::useless_conversion)]
+
 use pyo3::prelude::*;
 use pyo3::exceptions::PyValueError;
 use rayon::prelude::*;
 
+mod chunking;
+mod embeddings;
+mod language;
+mod similarity;
+mod tokenizer;
+mod vector_index;
+
+use chunking::chunk_text;
+use embeddings::train_pmi_embeddings;
+use language::{detect_language, detect_language_batch};
+use similarity::{find_near_duplicates, jaro_winkler, levenshtein, normalized_levenshtein};
+use tokenizer::{count_tokens, encode, fits_in_context};
+use vector_index::VectorIndex;
+
 // ============================================
 // 🎯 Basic Functions
 // ============================================
@@ -53,29 +75,15 @@ fn fibonacci(n: u64) -> PyResult<u64> {
 // ============================================
 
 /// Count tokens approximately (simplified tiktoken-like)
-/// Use this before sending to AI API to estimate costs
+/// Fast fallback when an exact `encoding` file isn't available -
+/// prefer `count_tokens` for accurate cost estimates and context guards.
 #[pyfunction]
 fn count_tokens_approx(text: &str) -> PyResult<usize> {
-    // Approximate: ~4 chars per token for English, ~2 for Korean
     let char_count = text.chars().count();
-    
-    // Check if mostly Korean (rough heuristic)
-    let korean_chars = text.chars().filter(|c| {
-        let code = *c as u32;
-        (0xAC00..=0xD7AF).contains(&code) || // Hangul Syllables
-        (0x1100..=0x11FF).contains(&code) || // Hangul Jamo
-        (0x3130..=0x318F).contains(&code)    // Hangul Compatibility Jamo
-    }).count();
-    
-    let korean_ratio = korean_chars as f64 / char_count.max(1) as f64;
-    
-    if korean_ratio > 0.3 {
-        // Korean: ~2 chars per token
-        Ok(char_count / 2 + 1)
-    } else {
-        // English: ~4 chars per token
-        Ok(char_count / 4 + 1)
-    }
+    let (language, _confidence) = language::detect_language(text)?;
+    let chars_per_token = language::chars_per_token_for(&language);
+
+    Ok((char_count as f64 / chars_per_token) as usize + 1)
 }
 
 /// Clean and normalize text for AI input
@@ -95,39 +103,9 @@ fn clean_text_for_ai(text: &str) -> PyResult<String> {
     Ok(result.trim().to_string())
 }
 
-/// Chunk text for AI processing (respects sentence boundaries)
-#[pyfunction]
-fn chunk_text(text: &str, max_tokens: usize) -> PyResult<Vec<String>> {
-    let sentences: Vec<&str> = text
-        .split(|c| c == '.' || c == '!' || c == '?' || c == '\n')
-        .filter(|s| !s.trim().is_empty())
-        .collect();
-    
-    let mut chunks: Vec<String> = Vec::new();
-    let mut current_chunk = String::new();
-    let mut current_tokens = 0;
-    
-    for sentence in sentences {
-        let sentence = sentence.trim();
-        let sentence_tokens = sentence.len() / 4 + 1; // approx
-        
-        if current_tokens + sentence_tokens > max_tokens && !current_chunk.is_empty() {
-            chunks.push(current_chunk.trim().to_string());
-            current_chunk = String::new();
-            current_tokens = 0;
-        }
-        
-        current_chunk.push_str(sentence);
-        current_chunk.push_str(". ");
-        current_tokens += sentence_tokens;
-    }
-    
-    if !current_chunk.trim().is_empty() {
-        chunks.push(current_chunk.trim().to_string());
-    }
-    
-    Ok(chunks)
-}
+// ============================================
+// 🔍 Fuzzy String Similarity (dedup)
+// ============================================
 
 // ============================================
 // 📊 Vector Operations (for embeddings)
@@ -224,13 +202,26 @@ fn daemon_one_core(m: &Bound<'_, PyModule>) -> PyResult<()> {
     
     // AI Preprocessing
     m.add_function(wrap_pyfunction!(count_tokens_approx, m)?)?;
+    m.add_function(wrap_pyfunction!(count_tokens, m)?)?;
+    m.add_function(wrap_pyfunction!(encode, m)?)?;
+    m.add_function(wrap_pyfunction!(fits_in_context, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_language, m)?)?;
+    m.add_function(wrap_pyfunction!(detect_language_batch, m)?)?;
     m.add_function(wrap_pyfunction!(clean_text_for_ai, m)?)?;
     m.add_function(wrap_pyfunction!(chunk_text, m)?)?;
-    
+
+    // Fuzzy String Similarity
+    m.add_function(wrap_pyfunction!(levenshtein, m)?)?;
+    m.add_function(wrap_pyfunction!(normalized_levenshtein, m)?)?;
+    m.add_function(wrap_pyfunction!(jaro_winkler, m)?)?;
+    m.add_function(wrap_pyfunction!(find_near_duplicates, m)?)?;
+
     // Vector Operations
     m.add_function(wrap_pyfunction!(cosine_similarity, m)?)?;
     m.add_function(wrap_pyfunction!(find_top_k_similar, m)?)?;
-    
+    m.add_function(wrap_pyfunction!(train_pmi_embeddings, m)?)?;
+    m.add_class::<VectorIndex>()?;
+
     // Financial
     m.add_function(wrap_pyfunction!(compound_interest, m)?)?;
     m.add_function(wrap_pyfunction!(batch_compound_interest, m)?)?;
@@ -0,0 +1,160 @@
+//! Fuzzy string similarity for dedup and near-duplicate detection.
+//!
+//! Catches typo-level and boilerplate duplicates among scraped/ingested
+//! documents before they reach chunking and embedding, which cosine
+//! similarity on embeddings doesn't catch.
+
+use pyo3::prelude::*;
+use rayon::prelude::*;
+
+/// Levenshtein edit distance between `a` and `b`, using the standard
+/// two-row dynamic-programming table (O(min(|a|,|b|)) memory).
+#[pyfunction]
+pub fn levenshtein(a: &str, b: &str) -> PyResult<usize> {
+    Ok(levenshtein_inner(a, b))
+}
+
+fn levenshtein_inner(a: &str, b: &str) -> usize {
+    let (a, b): (Vec<char>, Vec<char>) = if a.chars().count() <= b.chars().count() {
+        (a.chars().collect(), b.chars().collect())
+    } else {
+        (b.chars().collect(), a.chars().collect())
+    };
+
+    let mut prev_row: Vec<usize> = (0..=a.len()).collect();
+    let mut curr_row = vec![0usize; a.len() + 1];
+
+    for (j, &bc) in b.iter().enumerate() {
+        curr_row[0] = j + 1;
+        for (i, &ac) in a.iter().enumerate() {
+            let cost = if ac == bc { 0 } else { 1 };
+            curr_row[i + 1] = (prev_row[i + 1] + 1)
+                .min(curr_row[i] + 1)
+                .min(prev_row[i] + cost);
+        }
+        std::mem::swap(&mut prev_row, &mut curr_row);
+    }
+
+    prev_row[a.len()]
+}
+
+/// Levenshtein distance normalized into a `[0, 1]` similarity score, where
+/// `1.0` means identical strings.
+#[pyfunction]
+pub fn normalized_levenshtein(a: &str, b: &str) -> PyResult<f64> {
+    Ok(normalized_levenshtein_inner(a, b))
+}
+
+fn normalized_levenshtein_inner(a: &str, b: &str) -> f64 {
+    let max_len = a.chars().count().max(b.chars().count());
+    if max_len == 0 {
+        return 1.0;
+    }
+    1.0 - levenshtein_inner(a, b) as f64 / max_len as f64
+}
+
+/// Jaro-Winkler similarity in `[0, 1]`: the Jaro match/transposition score
+/// boosted by a 0.1 prefix scale over the first up-to-4 matching characters.
+#[pyfunction]
+pub fn jaro_winkler(a: &str, b: &str) -> PyResult<f64> {
+    Ok(jaro_winkler_inner(a, b))
+}
+
+fn jaro_winkler_inner(a: &str, b: &str) -> f64 {
+    let jaro = jaro_inner(a, b);
+    if jaro == 0.0 {
+        return 0.0;
+    }
+
+    let a_chars: Vec<char> = a.chars().collect();
+    let b_chars: Vec<char> = b.chars().collect();
+    let prefix_len = a_chars
+        .iter()
+        .zip(b_chars.iter())
+        .take(4)
+        .take_while(|(x, y)| x == y)
+        .count();
+
+    jaro + prefix_len as f64 * 0.1 * (1.0 - jaro)
+}
+
+fn jaro_inner(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    if a.is_empty() && b.is_empty() {
+        return 1.0;
+    }
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+
+    let match_distance = (a.len().max(b.len()) / 2).saturating_sub(1);
+
+    let mut a_matched = vec![false; a.len()];
+    let mut b_matched = vec![false; b.len()];
+    let mut matches = 0usize;
+
+    for i in 0..a.len() {
+        let lo = i.saturating_sub(match_distance);
+        let hi = (i + match_distance + 1).min(b.len());
+        for j in lo..hi {
+            if b_matched[j] || a[i] != b[j] {
+                continue;
+            }
+            a_matched[i] = true;
+            b_matched[j] = true;
+            matches += 1;
+            break;
+        }
+    }
+
+    if matches == 0 {
+        return 0.0;
+    }
+
+    let mut transpositions = 0usize;
+    let mut b_index = 0usize;
+    for i in 0..a.len() {
+        if !a_matched[i] {
+            continue;
+        }
+        while !b_matched[b_index] {
+            b_index += 1;
+        }
+        if a[i] != b[b_index] {
+            transpositions += 1;
+        }
+        b_index += 1;
+    }
+
+    let matches = matches as f64;
+    (matches / a.len() as f64
+        + matches / b.len() as f64
+        + (matches - (transpositions / 2) as f64) / matches)
+        / 3.0
+}
+
+/// Find all pairs of `texts` whose normalized-Levenshtein similarity is at
+/// or above `threshold`, parallelizing the pairwise comparison with rayon.
+/// Returns `(i, j, similarity)` triples with `i < j`.
+#[pyfunction]
+pub fn find_near_duplicates(
+    texts: Vec<String>,
+    threshold: f64,
+) -> PyResult<Vec<(usize, usize, f64)>> {
+    let pairs: Vec<(usize, usize)> = (0..texts.len())
+        .flat_map(|i| (i + 1..texts.len()).map(move |j| (i, j)))
+        .collect();
+
+    let mut matches: Vec<(usize, usize, f64)> = pairs
+        .par_iter()
+        .filter_map(|&(i, j)| {
+            let similarity = normalized_levenshtein_inner(&texts[i], &texts[j]);
+            (similarity >= threshold).then_some((i, j, similarity))
+        })
+        .collect();
+
+    matches.sort_by(|a, b| a.0.cmp(&b.0).then_with(|| a.1.cmp(&b.1)));
+    Ok(matches)
+}
@@ -0,0 +1,158 @@
+//! Exact BPE tokenization compatible with tiktoken-style encodings
+//! (e.g. `cl100k_base`).
+//!
+//! Loads a `<base64 token> <rank>` merges file — the format tiktoken ships
+//! its encodings in — and runs the standard byte-pair-merge loop: split the
+//! input on a regex pretokenizer pattern, map each piece to its UTF-8 bytes,
+//! then repeatedly merge the lowest-rank adjacent byte pair until no ranked
+//! pair remains.
+
+use std::collections::HashMap;
+use std::fs;
+use std::sync::OnceLock;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use fancy_regex::Regex;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+/// GPT-3.5/4 style pretokenizer pattern used by `cl100k_base`. The trailing
+/// `\s+(?!\S)` alternative is a negative lookahead (splits off trailing
+/// whitespace as its own piece unless it's followed by more whitespace),
+/// which the plain `regex` crate can't express - hence `fancy_regex` here.
+const PRETOKENIZE_PATTERN: &str =
+    r"'(?:[sdmt]|ll|ve|re)| ?\p{L}+| ?\p{N}+| ?[^\s\p{L}\p{N}]+|\s+(?!\S)|\s+";
+
+fn pretokenize_regex() -> &'static Regex {
+    static RE: OnceLock<Regex> = OnceLock::new();
+    RE.get_or_init(|| Regex::new(PRETOKENIZE_PATTERN).expect("valid pretokenizer pattern"))
+}
+
+/// A loaded BPE merge table: byte-string -> rank (lower rank merges first).
+pub(crate) struct Encoding {
+    ranks: HashMap<Vec<u8>, u32>,
+}
+
+impl Encoding {
+    /// Load a tiktoken-style `<base64 token> <rank>` merges file.
+    pub(crate) fn load(path: &str) -> PyResult<Self> {
+        let contents = fs::read_to_string(path).map_err(|e| {
+            PyValueError::new_err(format!("failed to read encoding file '{path}': {e}"))
+        })?;
+
+        let mut ranks = HashMap::new();
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            let mut parts = line.split_whitespace();
+            let token_b64 = parts
+                .next()
+                .ok_or_else(|| PyValueError::new_err("malformed encoding line: missing token"))?;
+            let rank: u32 = parts
+                .next()
+                .ok_or_else(|| PyValueError::new_err("malformed encoding line: missing rank"))?
+                .parse()
+                .map_err(|_| {
+                    PyValueError::new_err("malformed encoding line: rank is not an integer")
+                })?;
+            let token = BASE64
+                .decode(token_b64)
+                .map_err(|e| PyValueError::new_err(format!("malformed base64 token: {e}")))?;
+            ranks.insert(token, rank);
+        }
+
+        if ranks.is_empty() {
+            return Err(PyValueError::new_err(format!(
+                "encoding file '{path}' contained no ranked tokens"
+            )));
+        }
+
+        Ok(Self { ranks })
+    }
+
+    /// Encode text into token ids via the standard byte-pair-merge loop.
+    fn encode(&self, text: &str) -> PyResult<Vec<u32>> {
+        Ok(self.encode_with_spans(text)?.into_iter().map(|(id, _, _)| id).collect())
+    }
+
+    /// Encode text into `(token_id, start_byte, end_byte)` triples, where
+    /// byte offsets are absolute positions in `text`. Token byte spans never
+    /// overlap and exactly tile `text`, so callers can slice `text` directly
+    /// between any two token boundaries without corrupting it.
+    pub(crate) fn encode_with_spans(&self, text: &str) -> PyResult<Vec<(u32, usize, usize)>> {
+        let mut spans = Vec::new();
+        for piece in pretokenize_regex().find_iter(text) {
+            let piece = piece
+                .map_err(|e| PyValueError::new_err(format!("pretokenizer regex failed: {e}")))?;
+            spans.extend(self.bpe_encode_piece(piece.as_str().as_bytes(), piece.start()));
+        }
+        Ok(spans)
+    }
+
+    /// Merge a single pretokenized piece down to its final token ids, each
+    /// tagged with its absolute byte span (`piece_offset` is where this
+    /// piece starts within the original text).
+    fn bpe_encode_piece(&self, piece: &[u8], piece_offset: usize) -> Vec<(u32, usize, usize)> {
+        // Each part tracks the contiguous byte range of `piece` it covers,
+        // so a merge can concatenate adjacent ranges without losing track
+        // of where they sit in the original text.
+        let mut parts: Vec<(Vec<u8>, usize, usize)> = piece
+            .iter()
+            .enumerate()
+            .map(|(i, &b)| (vec![b], i, i + 1))
+            .collect();
+
+        while parts.len() > 1 {
+            let mut best: Option<(usize, u32)> = None;
+            for i in 0..parts.len() - 1 {
+                let mut pair = parts[i].0.clone();
+                pair.extend_from_slice(&parts[i + 1].0);
+                if let Some(&rank) = self.ranks.get(&pair) {
+                    if best.is_none_or(|(_, best_rank)| rank < best_rank) {
+                        best = Some((i, rank));
+                    }
+                }
+            }
+
+            let Some((i, _)) = best else { break };
+            let mut merged_bytes = parts[i].0.clone();
+            merged_bytes.extend_from_slice(&parts[i + 1].0);
+            let merged = (merged_bytes, parts[i].1, parts[i + 1].2);
+            parts.splice(i..=i + 1, [merged]);
+        }
+
+        parts
+            .into_iter()
+            .map(|(bytes, start, end)| {
+                let id = *self.ranks.get(&bytes).unwrap_or(&0);
+                (id, piece_offset + start, piece_offset + end)
+            })
+            .collect()
+    }
+}
+
+/// Exact token count for `text` under the BPE `encoding` file at `encoding_path`.
+///
+/// Prefer this over `count_tokens_approx` whenever an accurate cost estimate
+/// or context-limit check matters; it loads the encoding fresh each call so
+/// callers should batch work rather than calling this in a tight loop.
+#[pyfunction]
+pub fn count_tokens(text: &str, encoding_path: &str) -> PyResult<usize> {
+    Ok(Encoding::load(encoding_path)?.encode(text)?.len())
+}
+
+/// Encode `text` into BPE token ids under the given `encoding` file.
+#[pyfunction]
+pub fn encode(text: &str, encoding_path: &str) -> PyResult<Vec<u32>> {
+    Encoding::load(encoding_path)?.encode(text)
+}
+
+/// Guard against over-length prompts: true if `text` fits within `max_tokens`
+/// under `encoding_path`, mirroring the "remaining tokens" guard pattern.
+#[pyfunction]
+pub fn fits_in_context(text: &str, encoding_path: &str, max_tokens: usize) -> PyResult<bool> {
+    Ok(Encoding::load(encoding_path)?.encode(text)?.len() <= max_tokens)
+}
@@ -0,0 +1,363 @@
+//! Approximate nearest-neighbor index (HNSW) backing repeated top-k
+//! similarity queries, replacing the full `O(n*d)` scan + full sort that
+//! `find_top_k_similar` has to redo on every call.
+//!
+//! Vectors are stored in layers with exponentially decaying insertion
+//! probability; each node connects to its `m` nearest neighbors per layer.
+//! Queries descend greedily from the top layer, then run a bounded beam
+//! (`ef_search`) search at layer 0. Vectors are pre-normalized on insert so
+//! cosine similarity reduces to a dot product.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashSet};
+use std::fs;
+
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+
+const DEFAULT_M: usize = 16;
+const DEFAULT_EF_CONSTRUCTION: usize = 200;
+const DEFAULT_EF_SEARCH: usize = 50;
+
+/// Deterministic xorshift64 PRNG, used only to pick each inserted node's
+/// layer - not worth pulling in an external `rand` dependency for.
+struct Xorshift64(u64);
+
+impl Xorshift64 {
+    fn next_f64(&mut self) -> f64 {
+        let mut x = self.0;
+        x ^= x << 13;
+        x ^= x >> 7;
+        x ^= x << 17;
+        self.0 = x;
+        (x >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn normalize(vector: &[f64]) -> Vec<f64> {
+    let norm: f64 = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+    if norm == 0.0 {
+        vector.to_vec()
+    } else {
+        vector.iter().map(|x| x / norm).collect()
+    }
+}
+
+fn dot(a: &[f64], b: &[f64]) -> f64 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+struct Node {
+    id: i64,
+    vector: Vec<f64>,
+    /// `neighbors[layer]` holds this node's connections at that layer.
+    neighbors: Vec<Vec<usize>>,
+}
+
+#[derive(Clone, Copy)]
+struct ScoredNode {
+    similarity: f64,
+    idx: usize,
+}
+
+impl PartialEq for ScoredNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.similarity == other.similarity
+    }
+}
+impl Eq for ScoredNode {}
+impl PartialOrd for ScoredNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for ScoredNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.similarity.partial_cmp(&other.similarity).unwrap_or(Ordering::Equal)
+    }
+}
+
+/// A persistent HNSW approximate nearest-neighbor index over cosine
+/// similarity.
+#[pyclass]
+pub struct VectorIndex {
+    nodes: Vec<Node>,
+    entry_point: Option<usize>,
+    max_layer: usize,
+    m: usize,
+    ef_construction: usize,
+    ef_search: usize,
+    rng: Xorshift64,
+}
+
+impl VectorIndex {
+    fn level_multiplier(&self) -> f64 {
+        1.0 / (self.m as f64).ln()
+    }
+
+    fn random_level(&mut self) -> usize {
+        let uniform = self.rng.next_f64().max(f64::EPSILON);
+        (-uniform.ln() * self.level_multiplier()).floor() as usize
+    }
+
+    /// Greedy beam search for the `ef` nearest neighbors of `query` among
+    /// nodes reachable from `entry` at `layer`, sorted by descending
+    /// similarity.
+    fn search_layer(&self, entry: usize, query: &[f64], ef: usize, layer: usize) -> Vec<ScoredNode> {
+        let mut visited = HashSet::new();
+        visited.insert(entry);
+
+        let entry_similarity = dot(&self.nodes[entry].vector, query);
+        let entry_node = ScoredNode { similarity: entry_similarity, idx: entry };
+
+        let mut frontier = BinaryHeap::new();
+        frontier.push(entry_node);
+        let mut found: BinaryHeap<Reverse<ScoredNode>> = BinaryHeap::new();
+        found.push(Reverse(entry_node));
+
+        while let Some(current) = frontier.pop() {
+            let worst_found = found.peek().map(|Reverse(s)| s.similarity).unwrap_or(f64::NEG_INFINITY);
+            if found.len() >= ef && current.similarity < worst_found {
+                break;
+            }
+
+            let Some(layer_neighbors) = self.nodes[current.idx].neighbors.get(layer) else { continue };
+            for &neighbor in layer_neighbors {
+                if !visited.insert(neighbor) {
+                    continue;
+                }
+                let similarity = dot(&self.nodes[neighbor].vector, query);
+                let worst_found = found.peek().map(|Reverse(s)| s.similarity).unwrap_or(f64::NEG_INFINITY);
+                if found.len() < ef || similarity > worst_found {
+                    let candidate = ScoredNode { similarity, idx: neighbor };
+                    frontier.push(candidate);
+                    found.push(Reverse(candidate));
+                    if found.len() > ef {
+                        found.pop();
+                    }
+                }
+            }
+        }
+
+        let mut results: Vec<ScoredNode> = found.into_iter().map(|Reverse(s)| s).collect();
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+        results
+    }
+
+    fn insert(&mut self, id: i64, vector: Vec<f64>) {
+        let level = self.random_level();
+        let new_idx = self.nodes.len();
+        self.nodes.push(Node { id, vector: vector.clone(), neighbors: vec![Vec::new(); level + 1] });
+
+        let Some(mut entry) = self.entry_point else {
+            self.entry_point = Some(new_idx);
+            self.max_layer = level;
+            return;
+        };
+
+        let top_layer = self.max_layer;
+        for layer in (level + 1..=top_layer).rev() {
+            if let Some(best) = self.search_layer(entry, &vector, 1, layer).first() {
+                entry = best.idx;
+            }
+        }
+
+        for layer in (0..=level.min(top_layer)).rev() {
+            let candidates = self.search_layer(entry, &vector, self.ef_construction, layer);
+            let chosen: Vec<usize> = candidates.iter().take(self.m).map(|c| c.idx).collect();
+
+            for &neighbor in &chosen {
+                self.nodes[new_idx].neighbors[layer].push(neighbor);
+                self.nodes[neighbor].neighbors[layer].push(new_idx);
+                self.prune_neighbors(neighbor, layer);
+            }
+
+            if let Some(best) = candidates.first() {
+                entry = best.idx;
+            }
+        }
+
+        if level > top_layer {
+            self.entry_point = Some(new_idx);
+            self.max_layer = level;
+        }
+    }
+
+    /// Reject a vector/query whose dimension doesn't match vectors already
+    /// stored in the index.
+    fn check_dimension(&self, dim: usize) -> PyResult<()> {
+        if let Some(expected) = self.nodes.first().map(|n| n.vector.len()) {
+            if dim != expected {
+                return Err(PyValueError::new_err(format!(
+                    "expected vectors of dimension {expected}, got {dim}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep only `m` closest connections for `node_idx` at `layer`.
+    fn prune_neighbors(&mut self, node_idx: usize, layer: usize) {
+        if self.nodes[node_idx].neighbors[layer].len() <= self.m {
+            return;
+        }
+        let own_vector = self.nodes[node_idx].vector.clone();
+        let mut neighbors = std::mem::take(&mut self.nodes[node_idx].neighbors[layer]);
+        neighbors.sort_by(|&a, &b| {
+            dot(&self.nodes[b].vector, &own_vector)
+                .partial_cmp(&dot(&self.nodes[a].vector, &own_vector))
+                .unwrap()
+        });
+        neighbors.truncate(self.m);
+        self.nodes[node_idx].neighbors[layer] = neighbors;
+    }
+}
+
+#[pymethods]
+impl VectorIndex {
+    #[new]
+    #[pyo3(signature = (m=DEFAULT_M, ef_construction=DEFAULT_EF_CONSTRUCTION, ef_search=DEFAULT_EF_SEARCH))]
+    fn new(m: usize, ef_construction: usize, ef_search: usize) -> Self {
+        Self {
+            nodes: Vec::new(),
+            entry_point: None,
+            max_layer: 0,
+            m: m.max(2),
+            ef_construction: ef_construction.max(1),
+            ef_search: ef_search.max(1),
+            rng: Xorshift64(0x9E3779B97F4A7C15),
+        }
+    }
+
+    /// Insert `ids[i]` / `vectors[i]` pairs into the index.
+    fn add(&mut self, ids: Vec<i64>, vectors: Vec<Vec<f64>>) -> PyResult<()> {
+        if ids.len() != vectors.len() {
+            return Err(PyValueError::new_err("ids and vectors must have the same length"));
+        }
+        for vector in &vectors {
+            self.check_dimension(vector.len())?;
+        }
+        for (id, vector) in ids.into_iter().zip(vectors) {
+            self.insert(id, normalize(&vector));
+        }
+        Ok(())
+    }
+
+    /// Return the `k` nearest neighbor `(id, similarity)` pairs to `query`.
+    fn search(&self, query: Vec<f64>, k: usize) -> PyResult<Vec<(i64, f64)>> {
+        self.check_dimension(query.len())?;
+        let Some(mut entry) = self.entry_point else { return Ok(Vec::new()) };
+        let query = normalize(&query);
+
+        for layer in (1..=self.max_layer).rev() {
+            if let Some(best) = self.search_layer(entry, &query, 1, layer).first() {
+                entry = best.idx;
+            }
+        }
+
+        let ef = self.ef_search.max(k);
+        let candidates = self.search_layer(entry, &query, ef, 0);
+
+        // Bounded max-heap of size k, rather than sorting the full
+        // candidate list, for the final top-k selection.
+        let mut heap: BinaryHeap<Reverse<ScoredNode>> = BinaryHeap::new();
+        for candidate in candidates {
+            if heap.len() < k {
+                heap.push(Reverse(candidate));
+            } else if heap.peek().is_some_and(|Reverse(worst)| candidate.similarity > worst.similarity) {
+                heap.pop();
+                heap.push(Reverse(candidate));
+            }
+        }
+
+        let mut results: Vec<ScoredNode> = heap.into_iter().map(|Reverse(s)| s).collect();
+        results.sort_by(|a, b| b.similarity.partial_cmp(&a.similarity).unwrap());
+        Ok(results.into_iter().map(|s| (self.nodes[s.idx].id, s.similarity)).collect())
+    }
+
+    /// Persist the index to a plain-text file at `path`.
+    fn save(&self, path: &str) -> PyResult<()> {
+        let mut out = String::new();
+        out.push_str(&format!(
+            "{} {} {} {} {}\n",
+            self.m,
+            self.ef_construction,
+            self.ef_search,
+            self.entry_point.map(|e| e as i64).unwrap_or(-1),
+            self.max_layer,
+        ));
+
+        for node in &self.nodes {
+            let vector = node.vector.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(",");
+            let neighbors = node
+                .neighbors
+                .iter()
+                .map(|layer| layer.iter().map(|n| n.to_string()).collect::<Vec<_>>().join(","))
+                .collect::<Vec<_>>()
+                .join(";");
+            out.push_str(&format!("{}\t{}\t{}\n", node.id, vector, neighbors));
+        }
+
+        fs::write(path, out)
+            .map_err(|e| PyValueError::new_err(format!("failed to write index to '{path}': {e}")))
+    }
+
+    /// Load an index previously written by `save`.
+    #[staticmethod]
+    fn load(path: &str) -> PyResult<Self> {
+        let contents = fs::read_to_string(path)
+            .map_err(|e| PyValueError::new_err(format!("failed to read index from '{path}': {e}")))?;
+
+        let mut lines = contents.lines();
+        let header = lines
+            .next()
+            .ok_or_else(|| PyValueError::new_err("empty index file"))?;
+        let mut header_fields = header.split_whitespace();
+        let parse_field = |field: Option<&str>| -> PyResult<i64> {
+            field
+                .and_then(|f| f.parse().ok())
+                .ok_or_else(|| PyValueError::new_err("malformed index header"))
+        };
+        let m = parse_field(header_fields.next())? as usize;
+        let ef_construction = parse_field(header_fields.next())? as usize;
+        let ef_search = parse_field(header_fields.next())? as usize;
+        let entry_point_raw = parse_field(header_fields.next())?;
+        let max_layer = parse_field(header_fields.next())? as usize;
+
+        let mut nodes = Vec::new();
+        for line in lines {
+            if line.is_empty() {
+                continue;
+            }
+            let mut fields = line.split('\t');
+            let id: i64 = fields
+                .next()
+                .and_then(|f| f.parse().ok())
+                .ok_or_else(|| PyValueError::new_err("malformed index node: missing id"))?;
+            let vector: Vec<f64> = fields
+                .next()
+                .unwrap_or("")
+                .split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.parse().unwrap_or(0.0))
+                .collect();
+            let neighbors: Vec<Vec<usize>> = fields
+                .next()
+                .unwrap_or("")
+                .split(';')
+                .map(|layer| layer.split(',').filter_map(|s| s.parse().ok()).collect())
+                .collect();
+            nodes.push(Node { id, vector, neighbors });
+        }
+
+        Ok(Self {
+            nodes,
+            entry_point: if entry_point_raw < 0 { None } else { Some(entry_point_raw as usize) },
+            max_layer,
+            m,
+            ef_construction,
+            ef_search,
+            rng: Xorshift64(0x9E3779B97F4A7C15),
+        })
+    }
+}